@@ -31,10 +31,13 @@ use crate::bellman::plonk::better_better_cs::cs::{
 };
 
 use num_bigint::BigUint;
+use crate::num_bigint::BigInt;
 
 use super::super::allocated_num::{AllocatedNum, Num};
 use super::super::linear_combination::LinearCombination;
 use super::super::simple_term::Term;
+use super::super::boolean::{Boolean, AllocatedBit};
+use super::super::byte::Byte;
 
 use super::{U16RangeConstraintinSystem, constraint_num_bits};
 
@@ -273,6 +276,7 @@ pub fn split_into_fixed_number_of_limbs(mut fe: BigUint, bits_per_limb: usize, n
     limbs
 }
 
+#[derive(Clone)]
 pub struct LimbedBigUint<'a, E: Engine> {
     pub(crate) params: &'a LimbedRepresentationParameters<E>,
     pub(crate) num_limbs: usize,
@@ -294,14 +298,1367 @@ impl<'a, E: Engine> LimbedBigUint<'a, E> {
         result
     }
 
-    // pub fn reduce_if_necessary<CS: ConstraintSystem<E>>(
-    //     &mut self,
-    //     cs: &mut CS
-    // ) -> Result<(), SynthesisError> {
-    //     if self.is_constant {
+    // normalize every limb back to `limb_size_bits`, folding each limb's overflow
+    // into the next one. Afterwards every limb (including the carry leaving the
+    // top) is a clean `limb_size_bits` value, ready for another multiplication.
+    pub fn reduce<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        // a constant is already stored in canonical limb form
+        if self.is_constant {
+            return Ok(());
+        }
+
+        let params = self.params;
+        let limb_size = params.limb_size_bits;
+        let shift = params.shift_left_by_limb_constant;
+        let mut minus_shift = shift;
+        minus_shift.negate();
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        let modulus = BigUint::from(1u64) << limb_size;
+
+        let mut carry: Option<Num<E>> = None;
+        let mut carry_max = BigUint::from(0u64);
+        let mut carry_value = BigUint::from(0u64);
+
+        let mut new_limbs = Vec::with_capacity(self.limbs.len() + 1);
+
+        for limb in self.limbs.iter() {
+            let term_num = limb.term.collapse_into_num(cs)?;
+            let total_max = limb.max_value() + &carry_max;
+
+            // nothing to split off: the limb (plus incoming carry) already fits
+            if total_max < modulus {
+                let mut lc = LinearCombination::zero();
+                lc.add_assign_number_with_coeff(&term_num, E::Fr::one());
+                if let Some(ref c) = carry {
+                    lc.add_assign_number_with_coeff(c, E::Fr::one());
+                }
+                let collapsed = lc.into_num(cs)?;
+                new_limbs.push(Limb::new(Term::from_num(collapsed), total_max));
+                carry = None;
+                carry_max = BigUint::from(0u64);
+                carry_value = BigUint::from(0u64);
+                continue;
+            }
+
+            // witness `total = low + carry_out * 2^limb_size`
+            let (low_value, carry_out_value) = match limb.term.get_value() {
+                Some(_) => {
+                    let total = limb.get_value() + &carry_value;
+                    (Some(&total % &modulus), &total >> limb_size)
+                }
+                None => (None, BigUint::from(0u64)),
+            };
+
+            let carry_out_max = &total_max >> limb_size;
+            let carry_width = carry_out_max.bits() + 1;
+
+            let low = Self::alloc_limb(cs, low_value, limb_size)?;
+            let low_num = low.collapse_into_num(cs)?;
+            let carry_out = Self::alloc_limb(cs, Some(carry_out_value.clone()), carry_width)?;
+            let carry_out_num = carry_out.collapse_into_num(cs)?;
+
+            // term + carry_in - low - carry_out * 2^limb_size == 0
+            let mut lc = LinearCombination::zero();
+            lc.add_assign_number_with_coeff(&term_num, E::Fr::one());
+            if let Some(ref c) = carry {
+                lc.add_assign_number_with_coeff(c, E::Fr::one());
+            }
+            lc.add_assign_number_with_coeff(&low_num, minus_one);
+            lc.add_assign_number_with_coeff(&carry_out_num, minus_shift);
+            lc.enforce_zero(cs)?;
+
+            new_limbs.push(Limb::new(Term::from_num(low_num), params.limb_max_value.clone()));
+
+            carry = Some(carry_out_num);
+            carry_max = carry_out_max;
+            carry_value = carry_out_value;
+        }
+
+        // a carry leaving the most significant limb is itself split into
+        // canonical limbs: it was only range-constrained to `carry_width`, so
+        // decompose it into `limb_size_bits` limbs and pin the recomposition
+        if let Some(c) = carry {
+            if carry_max > BigUint::from(0u64) {
+                let n = num_limbs_for_bits(carry_max.bits(), limb_size);
+                if n == 1 {
+                    new_limbs.push(Limb::new(Term::from_num(c), carry_max));
+                } else {
+                    let carry_limb_values = match c.get_value() {
+                        Some(_) => split_into_fixed_number_of_limbs(carry_value.clone(), limb_size, n)
+                            .into_iter().map(Some).collect::<Vec<_>>(),
+                        None => vec![None; n],
+                    };
+
+                    let mut lc = LinearCombination::zero();
+                    let mut coeff = E::Fr::one();
+                    for (j, v) in carry_limb_values.into_iter().enumerate() {
+                        let limb_term = Self::alloc_limb(cs, v, limb_size)?;
+                        let limb_num = limb_term.collapse_into_num(cs)?;
+                        lc.add_assign_number_with_coeff(&limb_num, coeff);
+                        coeff.mul_assign(&shift);
+                        // the most significant sub-limb is bounded below limb_max_value
+                        let cap = core::cmp::min(params.limb_max_value.clone(), &carry_max >> (j * limb_size));
+                        new_limbs.push(Limb::new(Term::from_num(limb_num), cap));
+                    }
+                    // recomposed limbs must equal the original carry
+                    lc.add_assign_number_with_coeff(&c, minus_one);
+                    lc.enforce_zero(cs)?;
+                }
+            }
+        }
+
+        self.num_limbs = new_limbs.len();
+        self.limbs = new_limbs;
+
+        Ok(())
+    }
+
+    // reduce only when some limb's `max_bits()` has grown to within
+    // `limb_intermediate_value_capacity`; otherwise leave cheap accumulated
+    // additions in place.
+    pub fn reduce_if_necessary<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        if self.is_constant {
+            return Ok(());
+        }
+
+        let threshold = self.params.limb_intermediate_value_capacity;
+        let needs_reduction = self.limbs.iter_mut().any(|l| l.max_bits() >= threshold);
+
+        if needs_reduction {
+            self.reduce(cs)?;
+        }
+
+        Ok(())
+    }
+
+    // total upper bound on the integer value, obtained by summing the
+    // per-limb maxima shifted into their limb positions
+    pub fn max_value(&self) -> BigUint {
+        let shift = self.params.limb_size_bits;
+
+        let mut result = BigUint::from(0u64);
+
+        for l in self.limbs.iter().rev() {
+            result <<= shift;
+            result += l.max_value();
+        }
+
+        result
+    }
+
+    // allocate a limbed number from an optional integer witness, splitting it
+    // into `num_limbs` limbs of `limb_size_bits` and range-constraining each one
+    pub fn alloc<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        value: Option<BigUint>,
+        num_limbs: usize,
+        params: &'a LimbedRepresentationParameters<E>,
+    ) -> Result<Self, SynthesisError> {
+        let limb_values = match value {
+            Some(ref v) => split_into_fixed_number_of_limbs(v.clone(), params.limb_size_bits, num_limbs)
+                .into_iter().map(Some).collect::<Vec<_>>(),
+            None => vec![None; num_limbs],
+        };
+
+        let mut limbs = Vec::with_capacity(num_limbs);
+        for limb_value in limb_values.into_iter() {
+            let term = Self::alloc_limb(cs, limb_value, params.limb_size_bits)?;
+            limbs.push(Limb::new(term, params.limb_max_value.clone()));
+        }
+
+        Ok(Self {
+            params,
+            num_limbs,
+            limbs,
+            is_constant: false,
+        })
+    }
+
+    // a purely constant limbed number, carrying no constraints
+    pub fn new_constant(
+        value: BigUint,
+        num_limbs: usize,
+        params: &'a LimbedRepresentationParameters<E>,
+    ) -> Self {
+        let limb_values = split_into_fixed_number_of_limbs(value, params.limb_size_bits, num_limbs);
+
+        let limbs = limb_values.into_iter().map(|v| {
+            let fe = biguint_to_fe::<E::Fr>(v.clone());
+            Limb::new(Term::from_constant(fe), v)
+        }).collect();
+
+        Self {
+            params,
+            num_limbs,
+            limbs,
+            is_constant: true,
+        }
+    }
+
+    // allocate a single value of `width` bits as a `Term`, range-constraining it
+    // through the shared `U16RangeConstraintinSystem` machinery
+    fn alloc_limb<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        value: Option<BigUint>,
+        width: usize,
+    ) -> Result<Term<E>, SynthesisError> {
+        let allocated = AllocatedNum::alloc(cs, || {
+            Ok(biguint_to_fe::<E::Fr>(value.clone().ok_or(SynthesisError::AssignmentMissing)?))
+        })?;
+
+        constraint_num_bits(cs, &allocated, width)?;
+
+        Ok(Term::from_allocated_num(allocated))
+    }
+
+    // limb polynomial evaluated as a single native field element:
+    // `sum_i limb_i * (2^limb_size_bits)^i` reduced modulo `E::Fr::char()`
+    fn into_field_num<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+    ) -> Result<Num<E>, SynthesisError> {
+        let mut lc = LinearCombination::zero();
+        let mut coeff = E::Fr::one();
+
+        for limb in self.limbs.iter() {
+            let num = limb.term.collapse_into_num(cs)?;
+            lc.add_assign_number_with_coeff(&num, coeff);
+            coeff.mul_assign(&self.params.shift_left_by_limb_constant);
+        }
+
+        lc.into_num(cs)
+    }
+
+    // `self * other mod p`: witness `q, r` with `a*b = q*p + r`, `r < p` out of
+    // circuit and enforce the identity via CRT, modulo both `E::Fr` and `2^t`.
+    pub fn mul_mod<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+        p: &BigUint,
+    ) -> Result<Self, SynthesisError> {
+        let params = self.params;
+        let limb_size = params.limb_size_bits;
+
+        // the binary-leg carry bound below is derived from `limb_max_value`, so
+        // both operands must be in canonical limb form: limbs grown by earlier
+        // additions have to be run through `reduce` first (`reduce_if_necessary`
+        // only acts once a limb nears `limb_intermediate_value_capacity`).
+        assert!(self.limbs.iter().all(|l| l.max_value() <= params.limb_max_value), "self limbs must be reduced before mul_mod");
+        assert!(other.limbs.iter().all(|l| l.max_value() <= params.limb_max_value), "other limbs must be reduced before mul_mod");
+
+        // witness the quotient and remainder out of circuit
+        let (q_value, r_value) = match (self.maybe_value(), other.maybe_value()) {
+            (Some(a), Some(b)) => {
+                let product = a * b;
+                (Some(&product / p), Some(&product % p))
+            }
+            _ => (None, None),
+        };
+
+        // size bounds: `r < p` and `q <= max(a*b) / p`
+        let product_max = self.max_value() * other.max_value();
+        let q_max = &product_max / p;
+
+        let r_num_limbs = num_limbs_for_bits(p.bits(), limb_size);
+        let q_num_limbs = num_limbs_for_bits(q_max.bits() + 1, limb_size);
+
+        let r = Self::alloc(cs, r_value, r_num_limbs, params)?;
+        let q = Self::alloc(cs, q_value, q_num_limbs, params)?;
+
+        // the witnessed remainder must be reduced, and the quotient bounded
+        // tightly by `q_max` (not just to a whole number of limbs) so the
+        // binary-leg modulus leaves no room for an alternative `q`
+        r.enforce_strictly_less_than_constant(cs, p)?;
+        q.enforce_strictly_less_than_constant(cs, &(&q_max + BigUint::from(1u64)))?;
+
+        // native-field leg, then binary leg modulo `2^t` with `2^t * char > max(a*b)`
+        self.enforce_mul_relation_in_field(cs, other, &q, &r, p)?;
+        self.enforce_mul_relation_in_binary(cs, other, &q, &r, p, &product_max)?;
+
+        Ok(r)
+    }
+
+    // native-field leg of the CRT check: `A*B - Q*P - R == 0` in `E::Fr`, with
+    // each term the limb polynomial collapsed into a field element. Proves
+    // equality only modulo `E::Fr::char()`.
+    fn enforce_mul_relation_in_field<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+        q: &Self,
+        r: &Self,
+        p: &BigUint,
+    ) -> Result<(), SynthesisError> {
+        let a = self.into_field_num(cs)?;
+        let b = other.into_field_num(cs)?;
+        let q = q.into_field_num(cs)?;
+        let r = r.into_field_num(cs)?;
+
+        let ab = a.mul(cs, &b)?;
+
+        let char = repr_to_biguint::<E::Fr>(&E::Fr::char());
+        let mut p_fe = biguint_to_fe::<E::Fr>(p % &char);
+        p_fe.negate();
+
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        let mut lc = LinearCombination::zero();
+        lc.add_assign_number_with_coeff(&ab, E::Fr::one());
+        lc.add_assign_number_with_coeff(&q, p_fe);
+        lc.add_assign_number_with_coeff(&r, minus_one);
+        lc.enforce_zero(cs)?;
+
+        Ok(())
+    }
+
+    // binary leg of the CRT check: equality modulo `2^t`, via a signed carry
+    // chain over the low convolution coefficients of `a*b` and `q*p + r`. With
+    // the field leg and `2^t * char > max(a*b)` this pins the integer.
+    fn enforce_mul_relation_in_binary<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+        q: &Self,
+        r: &Self,
+        p: &BigUint,
+        product_max: &BigUint,
+    ) -> Result<(), SynthesisError> {
+        let params = self.params;
+        let limb_size = params.limb_size_bits;
+
+        let char = repr_to_biguint::<E::Fr>(&E::Fr::char());
+        let t_bits = product_max.bits().saturating_sub(char.bits()) + 1;
+        let num_binary_limbs = num_limbs_for_bits(t_bits, limb_size);
+
+        // constant limbs of the fixed modulus `p`
+        let p_limbs = split_into_fixed_number_of_limbs(p.clone(), limb_size, num_binary_limbs + q.limbs.len());
+
+        let a_nums = self.collapse_limbs(cs)?;
+        let b_nums = other.collapse_limbs(cs)?;
+        let q_nums = q.collapse_limbs(cs)?;
+        let r_nums = r.collapse_limbs(cs)?;
+
+        let shift = params.shift_left_by_limb_constant;
+        let mut minus_shift = shift;
+        minus_shift.negate();
+
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        // bound on the magnitude of the (signed) carry leaving a single position.
+        // A position contributes at most `limb_max^2 * num_cross_terms`, and the
+        // carry feeds back into the next position, so the true bound is the fixed
+        // point `M / (2^limb_size - 1)` rather than the floored `M >> limb_size`;
+        // take the ceiling so an honest witness is always rangeable.
+        let num_cross_terms = BigUint::from((self.limbs.len() + q.limbs.len()) as u64);
+        let numerator = &params.limb_max_value * &params.limb_max_value * &num_cross_terms;
+        let denom = (BigUint::from(1u64) << limb_size) - BigUint::from(1u64);
+        let carry_bound = (&numerator + &denom - BigUint::from(1u64)) / &denom;
+        let carry_width = carry_bound.bits() + 1;
+        let carry_bound_fe = biguint_to_fe::<E::Fr>(carry_bound.clone());
+
+        // running carry, carried as a `Num` holding the true (possibly negative)
+        // value; its magnitude is bounded by `carry_bound`
+        let mut carry: Option<Num<E>> = None;
+        let mut carry_value = BigInt::from(0u64);
+
+        for k in 0..num_binary_limbs {
+            // lhs - rhs at position `k`, plus the incoming carry
+            let mut lc = LinearCombination::zero();
+
+            // lhs coefficient: convolution of `a` and `b`
+            for i in 0..a_nums.len() {
+                if i <= k && k - i < b_nums.len() {
+                    let prod = a_nums[i].mul(cs, &b_nums[k - i])?;
+                    lc.add_assign_number_with_coeff(&prod, E::Fr::one());
+                }
+            }
+            // rhs coefficient: convolution of `q` and constant `p`, plus `r_k`
+            for i in 0..q_nums.len() {
+                if i <= k && k - i < p_limbs.len() {
+                    let mut coeff = biguint_to_fe::<E::Fr>(p_limbs[k - i].clone());
+                    coeff.negate();
+                    lc.add_assign_number_with_coeff(&q_nums[i], coeff);
+                }
+            }
+            if k < r_nums.len() {
+                lc.add_assign_number_with_coeff(&r_nums[k], minus_one);
+            }
+            if let Some(ref c) = carry {
+                lc.add_assign_number_with_coeff(c, E::Fr::one());
+            }
+
+            // this position's difference must be divisible by `2^limb_size`; the
+            // quotient is the outgoing carry. Witness it via a non-negative
+            // `shifted = carry_out + carry_bound` so it can be range-constrained,
+            // then expose the true carry as `shifted - carry_bound`.
+            let diff_value = self.binary_coeff_value(other, q, r, &p_limbs, k).map(|v| v + &carry_value);
+            let next_carry_value = diff_value.as_ref().map(|v| v >> limb_size);
+
+            let shifted_witness = next_carry_value.as_ref().map(|v| {
+                (v + BigInt::from(carry_bound.clone())).to_biguint().expect("shifted carry is non-negative")
+            });
+            let shifted = Self::alloc_limb(cs, shifted_witness, carry_width + 1)?;
+            let shifted_num = shifted.collapse_into_num(cs)?;
+
+            let mut carry_lc = LinearCombination::zero();
+            carry_lc.add_assign_number_with_coeff(&shifted_num, E::Fr::one());
+            carry_lc.add_assign_constant({
+                let mut b = carry_bound_fe;
+                b.negate();
+                b
+            });
+            let carry_out = carry_lc.into_num(cs)?;
+
+            // lhs - rhs + carry_in - carry_out * 2^limb_size == 0
+            lc.add_assign_number_with_coeff(&carry_out, minus_shift);
+            lc.enforce_zero(cs)?;
+
+            carry = Some(carry_out);
+            carry_value = next_carry_value.unwrap_or(BigInt::from(0u64));
+        }
+
+        Ok(())
+    }
+
+    // out-of-circuit value of the `a*b - q*p - r` convolution coefficient at a
+    // given limb position, used only to witness the carry chain
+    fn binary_coeff_value(
+        &self,
+        other: &Self,
+        q: &Self,
+        r: &Self,
+        p_limbs: &[BigUint],
+        k: usize,
+    ) -> Option<BigInt> {
+        let a = self.maybe_limb_values()?;
+        let b = other.maybe_limb_values()?;
+        let q = q.maybe_limb_values()?;
+        let r = r.maybe_limb_values()?;
+
+        let mut acc = BigInt::from(0u64);
+        for i in 0..a.len() {
+            if i <= k && k - i < b.len() {
+                acc += BigInt::from(&a[i] * &b[k - i]);
+            }
+        }
+        for i in 0..q.len() {
+            if i <= k && k - i < p_limbs.len() {
+                acc -= BigInt::from(&q[i] * &p_limbs[k - i]);
+            }
+        }
+        if k < r.len() {
+            acc -= BigInt::from(r[k].clone());
+        }
+
+        Some(acc)
+    }
+
+    // enforce `self < bound` for a constant bound by borrow-subtracting `self`
+    // from `bound - 1` and requiring no final borrow. Used to prove that a
+    // witnessed remainder is reduced modulo a fixed prime.
+    fn enforce_strictly_less_than_constant<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        bound: &BigUint,
+    ) -> Result<(), SynthesisError> {
+        let params = self.params;
+        let limb_size = params.limb_size_bits;
+
+        assert!(bound > &BigUint::from(0u64), "bound must be positive");
+        let top = bound - BigUint::from(1u64);
+        let top_limbs = split_into_fixed_number_of_limbs(top, limb_size, self.limbs.len());
+
+        let shift = params.shift_left_by_limb_constant;
+
+        let mut borrow_in: Option<Num<E>> = None;
+        let mut borrow_value = BigInt::from(0u64);
+
+        for (i, limb) in self.limbs.iter().enumerate() {
+            // top_i - self_i - borrow_in == diff_i - borrow_out * 2^limb_size
+            let self_num = limb.term.collapse_into_num(cs)?;
+
+            let diff_value = self.maybe_value().map(|_| {
+                let mut v = BigInt::from(top_limbs[i].clone());
+                v -= BigInt::from(limb.get_value());
+                v -= &borrow_value;
+                v
+            });
+            let borrow_out_value = diff_value.as_ref().map(|v| if v < &BigInt::from(0u64) { BigInt::from(1u64) } else { BigInt::from(0u64) });
+            let reduced_value = match (diff_value.as_ref(), borrow_out_value.as_ref()) {
+                (Some(d), Some(b)) => Some((d + (b << limb_size)).to_biguint().expect("reduced diff is non-negative")),
+                _ => None,
+            };
+
+            let diff = Self::alloc_limb(cs, reduced_value, limb_size)?;
+            let diff_num = diff.collapse_into_num(cs)?;
+
+            let borrow_out = AllocatedNum::alloc(cs, || {
+                Ok(biguint_to_fe::<E::Fr>(borrow_out_value.clone().and_then(|v| v.to_biguint()).ok_or(SynthesisError::AssignmentMissing)?))
+            })?;
+            // borrow_out is boolean
+            borrow_out.assert_bit(cs)?;
+            let borrow_out_num = Num::Variable(borrow_out);
+
+            let mut lc = LinearCombination::zero();
+            lc.add_assign_constant(biguint_to_fe::<E::Fr>(top_limbs[i].clone()));
+            let mut minus_one = E::Fr::one();
+            minus_one.negate();
+            lc.add_assign_number_with_coeff(&self_num, minus_one);
+            if let Some(ref b) = borrow_in {
+                lc.add_assign_number_with_coeff(b, minus_one);
+            }
+            lc.add_assign_number_with_coeff(&diff_num, minus_one);
+            lc.add_assign_number_with_coeff(&borrow_out_num, shift);
+            lc.enforce_zero(cs)?;
+
+            borrow_in = Some(borrow_out_num);
+            borrow_value = borrow_out_value.unwrap_or(BigInt::from(0u64));
+        }
+
+        // no borrow may leave the top limb: self <= bound - 1 < bound
+        if let Some(b) = borrow_in {
+            b.enforce_equal(cs, &Num::Constant(E::Fr::zero()))?;
+        }
+
+        Ok(())
+    }
+
+    // `self^{-1} mod p`: witness the inverse out of circuit and enforce
+    // `self * w == 1 (mod p)` through `mul_mod`. A zero input has no inverse — it
+    // is rejected eagerly when the witness is known and is unsatisfiable anyway.
+    pub fn inverse_mod<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        p: &BigUint,
+    ) -> Result<Self, SynthesisError> {
+        let params = self.params;
+
+        if let Some(value) = self.maybe_value() {
+            if (&value % p) == BigUint::from(0u64) {
+                return Err(SynthesisError::DivisionByZero);
+            }
+        }
+
+        let w_value = self.maybe_value().map(|value| mod_inverse(&(&value % p), p));
+        let num_limbs = num_limbs_for_bits(p.bits(), params.limb_size_bits);
+
+        let w = Self::alloc(cs, w_value, num_limbs, params)?;
+        // the witnessed inverse must be reduced before use
+        w.enforce_strictly_less_than_constant(cs, p)?;
+
+        let product = self.mul_mod(cs, &w, p)?;
+        product.enforce_equal_to_constant(cs, &BigUint::from(1u64))?;
+
+        Ok(w)
+    }
+
+    // `self / divisor mod p`: witness the quotient out of circuit and enforce
+    // `divisor * result == self (mod p)`, both sides reduced through `mul_mod`.
+    pub fn div_mod<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        divisor: &Self,
+        p: &BigUint,
+    ) -> Result<Self, SynthesisError> {
+        let params = self.params;
+
+        if let Some(value) = divisor.maybe_value() {
+            if (&value % p) == BigUint::from(0u64) {
+                return Err(SynthesisError::DivisionByZero);
+            }
+        }
+
+        let result_value = match (self.maybe_value(), divisor.maybe_value()) {
+            (Some(num), Some(den)) => {
+                let inv = mod_inverse(&(&den % p), p);
+                Some((&num % p) * inv % p)
+            }
+            _ => None,
+        };
+        let num_limbs = num_limbs_for_bits(p.bits(), params.limb_size_bits);
+
+        let result = Self::alloc(cs, result_value, num_limbs, params)?;
+        result.enforce_strictly_less_than_constant(cs, p)?;
+
+        // divisor * result == self  (mod p)
+        let one = Self::new_constant(BigUint::from(1u64), 1, params);
+        let left = divisor.mul_mod(cs, &result, p)?;
+        let right = self.mul_mod(cs, &one, p)?;
+        left.enforce_equal_limbs(cs, &right)?;
+
+        Ok(result)
+    }
+
+    // serialize to a little-endian vector of exactly `ceil(bit_length / 8)`
+    // bytes. Each byte is a fresh 8-bit witness; the bytes belonging to a limb
+    // are enforced to recompose it, so bytes left unrepresented above
+    // `bit_length` force the number's high part to zero.
+    pub fn into_bytes_le<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        bit_length: usize,
+    ) -> Result<Vec<Byte<E>>, SynthesisError> {
+        let limb_size = self.params.limb_size_bits;
+        if limb_size % 8 != 0 || bit_length == 0 {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let bytes_per_limb = limb_size / 8;
+        let num_bytes = (bit_length + 7) / 8;
+        let capacity_bytes = self.limbs.len() * bytes_per_limb;
+
+        let value = self.maybe_value();
+
+        // one 8-bit witness per output byte
+        let mut bytes = Vec::with_capacity(num_bytes);
+        for j in 0..num_bytes {
+            let byte_value = value.as_ref().map(|v| ((v >> (8 * j)) % BigUint::from(256u64)).to_bytes_le()[0]);
+            bytes.push(Self::alloc_byte(cs, byte_value)?);
+        }
+
+        // a non-byte-aligned width forces the high sub-byte bits of the top byte
+        // to zero; skip it when that byte is already pinned to zero below
+        if bit_length % 8 != 0 && num_bytes - 1 < capacity_bytes {
+            match bytes[num_bytes - 1].into_num() {
+                Num::Variable(ref a) => constraint_num_bits(cs, a, bit_length % 8)?,
+                Num::Constant(c) => {
+                    if (fe_to_biguint(&c) >> (bit_length % 8)) != BigUint::from(0u64) {
+                        return Err(SynthesisError::Unsatisfiable);
+                    }
+                }
+            }
+        }
+
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+        let byte_shift = biguint_to_fe::<E::Fr>(BigUint::from(256u64));
+
+        // each limb must equal the recomposition of the bytes that fall in it;
+        // a limb whose bytes are (partly) missing is thereby forced to zero there
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let limb_num = limb.term.collapse_into_num(cs)?;
+
+            let mut lc = LinearCombination::zero();
+            let mut coeff = E::Fr::one();
+            for k in 0..bytes_per_limb {
+                let j = i * bytes_per_limb + k;
+                if j < num_bytes {
+                    lc.add_assign_number_with_coeff(&bytes[j].into_num(), coeff);
+                }
+                coeff.mul_assign(&byte_shift);
+            }
+
+            lc.add_assign_number_with_coeff(&limb_num, minus_one);
+            lc.enforce_zero(cs)?;
+        }
+
+        // output bytes above the limb capacity are not pinned by any limb, so
+        // force them to zero to keep them from being malleable witnesses
+        for byte in bytes.iter().skip(capacity_bytes) {
+            byte.into_num().enforce_equal(cs, &Num::Constant(E::Fr::zero()))?;
+        }
+
+        Ok(bytes)
+    }
+
+    // big-endian counterpart of `into_bytes_le`
+    pub fn into_bytes_be<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        bit_length: usize,
+    ) -> Result<Vec<Byte<E>>, SynthesisError> {
+        let mut bytes = self.into_bytes_le(cs, bit_length)?;
+        bytes.reverse();
+
+        Ok(bytes)
+    }
+
+    // inverse of `into_bytes_le`: group `ceil(bit_length / 8)` little-endian
+    // bytes into limbs. Full limbs are recomposed directly; the top limb is
+    // range-constrained to the remaining `bit_length` bits so the result never
+    // exceeds the declared modulus width.
+    pub fn from_bytes_le<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        bytes: &[Byte<E>],
+        bit_length: usize,
+        params: &'a LimbedRepresentationParameters<E>,
+    ) -> Result<Self, SynthesisError> {
+        let limb_size = params.limb_size_bits;
+        if limb_size % 8 != 0 || bit_length == 0 {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let bytes_per_limb = limb_size / 8;
+        let num_bytes = (bit_length + 7) / 8;
+        if bytes.len() != num_bytes {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let num_limbs = num_limbs_for_bits(bit_length, limb_size);
+        let top_bits = bit_length - (num_limbs - 1) * limb_size;
+
+        let byte_shift = biguint_to_fe::<E::Fr>(BigUint::from(256u64));
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        let mut limbs = Vec::with_capacity(num_limbs);
+        for (i, chunk) in bytes.chunks(bytes_per_limb).enumerate() {
+            let mut lc = LinearCombination::zero();
+            let mut coeff = E::Fr::one();
+            for byte in chunk.iter() {
+                lc.add_assign_number_with_coeff(&byte.into_num(), coeff);
+                coeff.mul_assign(&byte_shift);
+            }
+
+            if i + 1 < num_limbs {
+                // a full limb: the byte range constraints already cap it
+                let num = lc.into_num(cs)?;
+                limbs.push(Limb::new(Term::from_num(num), params.limb_max_value.clone()));
+            } else {
+                // the top limb: pin it to a witness range-constrained to `top_bits`
+                let top_value = Self::collapse_byte_value(chunk);
+                let top = Self::alloc_limb(cs, top_value, top_bits)?;
+                let top_num = top.collapse_into_num(cs)?;
+                lc.add_assign_number_with_coeff(&top_num, minus_one);
+                lc.enforce_zero(cs)?;
+                let top_max = (BigUint::from(1u64) << top_bits) - BigUint::from(1u64);
+                limbs.push(Limb::new(Term::from_num(top_num), top_max));
+            }
+        }
+
+        Ok(Self {
+            params,
+            num_limbs,
+            limbs,
+            is_constant: false,
+        })
+    }
+
+    // big-endian counterpart of `from_bytes_le`
+    pub fn from_bytes_be<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        bytes: &[Byte<E>],
+        bit_length: usize,
+        params: &'a LimbedRepresentationParameters<E>,
+    ) -> Result<Self, SynthesisError> {
+        let mut le = bytes.to_vec();
+        le.reverse();
+
+        Self::from_bytes_le(cs, &le, bit_length, params)
+    }
+
+    // out-of-circuit value of a little-endian byte chunk, if every byte is known
+    fn collapse_byte_value(chunk: &[Byte<E>]) -> Option<BigUint> {
+        let mut acc = BigUint::from(0u64);
+        for (k, byte) in chunk.iter().enumerate() {
+            let v = fe_to_biguint(&byte.into_num().get_value()?);
+            acc += v << (8 * k);
+        }
+
+        Some(acc)
+    }
+
+    // allocate a single byte witness, range-constrained to 8 bits
+    fn alloc_byte<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        value: Option<u8>,
+    ) -> Result<Byte<E>, SynthesisError> {
+        let allocated = AllocatedNum::alloc(cs, || {
+            Ok(biguint_to_fe::<E::Fr>(BigUint::from(value.ok_or(SynthesisError::AssignmentMissing)? as u64)))
+        })?;
+
+        constraint_num_bits(cs, &allocated, 8)?;
+
+        Ok(Byte::from_num(Num::Variable(allocated)))
+    }
+
+    // `base^exp mod p` for a big-endian exponent (`exp_bits[0]` most
+    // significant), via fixed-window square-and-multiply at the default width.
+    pub fn pow_mod<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        base: &Self,
+        exp_bits: &[Boolean],
+        p: &BigUint,
+    ) -> Result<Self, SynthesisError> {
+        Self::pow_mod_with_window(cs, base, exp_bits, p, DEFAULT_EXP_WINDOW)
+    }
+
+    // windowed square-and-multiply with an explicit window width `k` (`k == 1`
+    // is plain square-and-multiply). Every product is passed through
+    // `reduce_if_necessary` so limbs stay below `limb_intermediate_value_capacity`.
+    pub fn pow_mod_with_window<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        base: &Self,
+        exp_bits: &[Boolean],
+        p: &BigUint,
+        window: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(window >= 1, "window width must be positive");
+
+        let params = base.params;
+        let num_limbs = num_limbs_for_bits(p.bits(), params.limb_size_bits);
+
+        // precompute base^0 .. base^(2^window - 1)
+        let table_size = 1usize << window;
+        let mut table = Vec::with_capacity(table_size);
+        table.push(Self::new_constant(BigUint::from(1u64), num_limbs, params));
+        for i in 1..table_size {
+            let mut next = table[i - 1].mul_mod(cs, base, p)?;
+            next.reduce_if_necessary(cs)?;
+            table.push(next);
+        }
+
+        // pad the exponent with leading zero bits to a whole number of windows
+        let pad = (window - exp_bits.len() % window) % window;
+        let mut bits = vec![Boolean::constant(false); pad];
+        bits.extend_from_slice(exp_bits);
+
+        let mut acc = Self::new_constant(BigUint::from(1u64), num_limbs, params);
+
+        for chunk in bits.chunks(window) {
+            // k squarings
+            for _ in 0..window {
+                acc = acc.mul_mod(cs, &acc, p)?;
+                acc.reduce_if_necessary(cs)?;
+            }
+            // multiply by the selected precomputed power of `base`
+            let selected = Self::select_from_table(cs, &table, chunk)?;
+            acc = acc.mul_mod(cs, &selected, p)?;
+            acc.reduce_if_necessary(cs)?;
+        }
 
-    //     }
-    // }
+        Ok(acc)
+    }
+
+    // multiplexer over `bits` (big-endian within the window) selecting the entry
+    // of `table` whose index equals the bits read as an integer
+    fn select_from_table<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        table: &[Self],
+        bits: &[Boolean],
+    ) -> Result<Self, SynthesisError> {
+        let mut level = table.to_vec();
+
+        // fold one bit at a time, least significant first
+        for bit in bits.iter().rev() {
+            let half = level.len() / 2;
+            let mut next = Vec::with_capacity(half);
+            for i in 0..half {
+                next.push(Self::conditionally_select(cs, bit, &level[2 * i + 1], &level[2 * i])?);
+            }
+            level = next;
+        }
+
+        Ok(level.into_iter().next().expect("table is non-empty"))
+    }
+
+    // select `a` when `flag` is set and `b` otherwise, limb by limb
+    pub fn conditionally_select<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        flag: &Boolean,
+        a: &Self,
+        b: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let len = core::cmp::max(a.limbs.len(), b.limbs.len());
+        let mut limbs = Vec::with_capacity(len);
+
+        for i in 0..len {
+            let a_num = if i < a.limbs.len() { a.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+            let b_num = if i < b.limbs.len() { b.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+
+            let selected = Num::conditionally_select(cs, flag, &a_num, &b_num)?;
+
+            let a_max = a.limbs.get(i).map(|l| l.max_value()).unwrap_or_else(|| BigUint::from(0u64));
+            let b_max = b.limbs.get(i).map(|l| l.max_value()).unwrap_or_else(|| BigUint::from(0u64));
+            let max = core::cmp::max(a_max, b_max);
+
+            limbs.push(Limb::new(Term::from_num(selected), max));
+        }
+
+        Ok(Self {
+            params: a.params,
+            num_limbs: len,
+            limbs,
+            is_constant: false,
+        })
+    }
+
+    // `true` iff the whole number is zero, i.e. every limb is zero. Computed as
+    // the conjunction of the per-limb zero flags.
+    pub fn is_zero<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+    ) -> Result<Boolean, SynthesisError> {
+        let mut result = Boolean::constant(true);
+
+        for limb in self.limbs.iter() {
+            let num = limb.term.collapse_into_num(cs)?;
+            let limb_is_zero = num.is_zero(cs)?;
+            result = Boolean::and(cs, &result, &limb_is_zero)?;
+        }
+
+        Ok(result)
+    }
+
+    // `true` iff `self` and `other` represent the same integer. Both operands are
+    // reduced to canonical limb form first so the comparison is limb-wise.
+    pub fn equals<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> Result<Boolean, SynthesisError> {
+        let mut a = self.clone();
+        a.reduce(cs)?;
+        let mut b = other.clone();
+        b.reduce(cs)?;
+
+        let len = core::cmp::max(a.limbs.len(), b.limbs.len());
+        let mut result = Boolean::constant(true);
+
+        for i in 0..len {
+            let a_num = if i < a.limbs.len() { a.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+            let b_num = if i < b.limbs.len() { b.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+            let eq = Num::equals(cs, &a_num, &b_num)?;
+            result = Boolean::and(cs, &result, &eq)?;
+        }
+
+        Ok(result)
+    }
+
+    // `true` iff `self < other`, obtained by borrow-subtracting `other` from
+    // `self`: a borrow leaving the most significant limb means `self < other`.
+    pub fn less_than<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> Result<Boolean, SynthesisError> {
+        self.borrow_subtract(cs, other)
+    }
+
+    // enforce `self < bound`, i.e. that borrowing occurs when subtracting `bound`
+    // from `self`
+    pub fn enforce_less_than<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        bound: &Self,
+    ) -> Result<(), SynthesisError> {
+        let is_less = self.less_than(cs, bound)?;
+        Boolean::enforce_equal(cs, &is_less, &Boolean::constant(true))
+    }
+
+    // borrow-propagating subtraction `self - other`, returning the final borrow
+    // bit (set iff `self < other`). Operands are reduced first; each position
+    // witnesses a borrow bit and a range-constrained difference limb enforcing
+    // `self_i - other_i - borrow_in == diff_i - borrow_out * 2^limb_size`.
+    fn borrow_subtract<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> Result<Boolean, SynthesisError> {
+        let params = self.params;
+        let limb_size = params.limb_size_bits;
+
+        let mut a = self.clone();
+        a.reduce(cs)?;
+        let mut b = other.clone();
+        b.reduce(cs)?;
+
+        let a_vals = a.maybe_limb_values();
+        let b_vals = b.maybe_limb_values();
+
+        let shift = params.shift_left_by_limb_constant;
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        let len = core::cmp::max(a.limbs.len(), b.limbs.len());
+
+        let mut borrow = Boolean::constant(false);
+        let mut borrow_value = BigInt::from(0u64);
+
+        for i in 0..len {
+            let a_num = if i < a.limbs.len() { a.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+            let b_num = if i < b.limbs.len() { b.limbs[i].term.collapse_into_num(cs)? } else { Num::Constant(E::Fr::zero()) };
+
+            let (diff_value, borrow_out_value) = match (&a_vals, &b_vals) {
+                (Some(av), Some(bv)) => {
+                    let ai = av.get(i).cloned().unwrap_or_else(|| BigUint::from(0u64));
+                    let bi = bv.get(i).cloned().unwrap_or_else(|| BigUint::from(0u64));
+                    let mut d = BigInt::from(ai) - BigInt::from(bi) - &borrow_value;
+                    let bo = d < BigInt::from(0u64);
+                    if bo {
+                        d += BigInt::from(1u64) << limb_size;
+                    }
+                    (Some(d.to_biguint().expect("difference is non-negative")), Some(bo))
+                }
+                _ => (None, None),
+            };
+
+            let diff = Self::alloc_limb(cs, diff_value, limb_size)?;
+            let diff_num = diff.collapse_into_num(cs)?;
+
+            let borrow_out = Boolean::from(AllocatedBit::alloc(cs, borrow_out_value)?);
+
+            // self_i - other_i - borrow_in - diff_i + borrow_out * 2^limb_size == 0
+            let mut lc = LinearCombination::zero();
+            lc.add_assign_number_with_coeff(&a_num, E::Fr::one());
+            lc.add_assign_number_with_coeff(&b_num, minus_one);
+            lc.add_assign_boolean_with_coeff(&borrow, minus_one);
+            lc.add_assign_number_with_coeff(&diff_num, minus_one);
+            lc.add_assign_boolean_with_coeff(&borrow_out, shift);
+            lc.enforce_zero(cs)?;
+
+            borrow = borrow_out;
+            borrow_value = borrow_out_value.map(|b| if b { BigInt::from(1u64) } else { BigInt::from(0u64) }).unwrap_or_else(|| BigInt::from(0u64));
+        }
+
+        Ok(borrow)
+    }
+
+    // enforce that `self` equals a constant integer, limb by limb. Both sides are
+    // assumed reduced (canonical limb form), which holds for the remainders
+    // produced by `mul_mod`.
+    fn enforce_equal_to_constant<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        value: &BigUint,
+    ) -> Result<(), SynthesisError> {
+        let limbs = split_into_fixed_number_of_limbs(value.clone(), self.params.limb_size_bits, self.limbs.len());
+
+        for (limb, expected) in self.limbs.iter().zip(limbs.into_iter()) {
+            let num = limb.term.collapse_into_num(cs)?;
+            num.enforce_equal(cs, &Num::Constant(biguint_to_fe::<E::Fr>(expected)))?;
+        }
+
+        Ok(())
+    }
+
+    // enforce that two reduced limbed numbers are equal, padding the shorter one
+    // with implicit zero limbs
+    fn enforce_equal_limbs<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> Result<(), SynthesisError> {
+        let len = core::cmp::max(self.limbs.len(), other.limbs.len());
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        for i in 0..len {
+            let mut lc = LinearCombination::zero();
+            if i < self.limbs.len() {
+                let num = self.limbs[i].term.collapse_into_num(cs)?;
+                lc.add_assign_number_with_coeff(&num, E::Fr::one());
+            }
+            if i < other.limbs.len() {
+                let num = other.limbs[i].term.collapse_into_num(cs)?;
+                lc.add_assign_number_with_coeff(&num, minus_one);
+            }
+            lc.enforce_zero(cs)?;
+        }
+
+        Ok(())
+    }
+
+    // collapse every limb into a `Num`, preserving limb order
+    fn collapse_limbs<CS: ConstraintSystem<E>>(
+        &self,
+        cs: &mut CS,
+    ) -> Result<Vec<Num<E>>, SynthesisError> {
+        self.limbs.iter().map(|l| l.term.collapse_into_num(cs)).collect()
+    }
+
+    // full integer value if every limb is assigned, otherwise `None`
+    fn maybe_value(&self) -> Option<BigUint> {
+        for limb in self.limbs.iter() {
+            limb.term.get_value()?;
+        }
+
+        Some(self.get_value())
+    }
+
+    // per-limb integer values if every limb is assigned
+    fn maybe_limb_values(&self) -> Option<Vec<BigUint>> {
+        self.limbs.iter().map(|l| l.term.get_value().map(|_| l.get_value())).collect()
+    }
+}
+
+// default fixed-window width for `pow_mod`
+const DEFAULT_EXP_WINDOW: usize = 4;
+
+// number of `limb_size`-bit limbs needed to hold a `num_bits`-bit value
+pub(crate) fn num_limbs_for_bits(num_bits: usize, limb_size: usize) -> usize {
+    if num_bits == 0 {
+        return 1;
+    }
+    (num_bits + limb_size - 1) / limb_size
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bellman::pairing::bn256::Bn256;
+    use crate::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, PlonkCsWidth4WithNextStepParams,
+    };
+
+    type Cs = TrivialAssembly<Bn256, PlonkCsWidth4WithNextStepParams, Width4MainGateWithDNextEquation>;
+
+    fn new_cs() -> Cs {
+        TrivialAssembly::new()
+    }
+
+    fn params() -> LimbedRepresentationParameters<Bn256> {
+        LimbedRepresentationParameters::new(16, 80)
+    }
+
+    fn alloc<'a>(
+        cs: &mut Cs,
+        value: u64,
+        num_limbs: usize,
+        params: &'a LimbedRepresentationParameters<Bn256>,
+    ) -> LimbedBigUint<'a, Bn256> {
+        LimbedBigUint::alloc(cs, Some(BigUint::from(value)), num_limbs, params).unwrap()
+    }
+
+    #[test]
+    fn mul_mod_known_answer() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(5u64);
+        let a = alloc(&mut cs, 3, 1, &params);
+        let b = alloc(&mut cs, 4, 1, &params);
+
+        let r = a.mul_mod(&mut cs, &b, &p).unwrap();
+        assert_eq!(r.get_value(), BigUint::from(2u64));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn mul_mod_rejects_wrong_remainder() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(97u64);
+        let a = alloc(&mut cs, 11, 1, &params);
+        let b = alloc(&mut cs, 13, 1, &params);
+
+        // 11 * 13 = 143 = 46 (mod 97); pinning the result to 45 is unsatisfiable
+        let r = a.mul_mod(&mut cs, &b, &p).unwrap();
+        r.enforce_equal_to_constant(&mut cs, &BigUint::from(45u64)).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn reduce_canonicalizes_limbs() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // a single limb whose value has grown far past `limb_max_value`, so its
+        // residual carry spans several limbs (exercises the multi-limb split)
+        let value = BigUint::from(1_000_000_000_000u64);
+        let mut number = alloc(&mut cs, 0, 1, &params);
+        number.limbs[0] = Limb::new(
+            Term::from_num(Num::Constant(biguint_to_fe::<crate::bellman::pairing::bn256::Fr>(value.clone()))),
+            value.clone(),
+        );
+
+        number.reduce(&mut cs).unwrap();
+        assert!(number.limbs.len() > 2);
+        assert!(number.limbs.iter().all(|l| l.max_value() <= params.limb_max_value));
+        assert_eq!(number.get_value(), value);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn inverse_mod_round_trip() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(97u64);
+        let a = alloc(&mut cs, 42, 1, &params);
+
+        let w = a.inverse_mod(&mut cs, &p).unwrap();
+        // 42 * 74 = 3108 = 32*97 + 4 ... check the defining relation instead
+        let product = a.mul_mod(&mut cs, &w, &p).unwrap();
+        assert_eq!(product.get_value(), BigUint::from(1u64));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn inverse_mod_rejects_zero() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(97u64);
+        let zero = alloc(&mut cs, 0, 1, &params);
+        assert!(zero.inverse_mod(&mut cs, &p).is_err());
+    }
+
+    #[test]
+    fn div_mod_known_answer() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(97u64);
+        let num = alloc(&mut cs, 6, 1, &params);
+        let den = alloc(&mut cs, 3, 1, &params);
+
+        let q = num.div_mod(&mut cs, &den, &p).unwrap();
+        assert_eq!(q.get_value(), BigUint::from(2u64));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn pow_mod_known_answer() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let p = BigUint::from(7u64);
+        let base = alloc(&mut cs, 3, 1, &params);
+        // exponent 5 = 0b101, most significant bit first
+        let exp_bits = vec![Boolean::constant(true), Boolean::constant(false), Boolean::constant(true)];
+
+        // 3^5 = 243 = 5 (mod 7)
+        let result = LimbedBigUint::pow_mod(&mut cs, &base, &exp_bits, &p).unwrap();
+        assert_eq!(result.get_value(), BigUint::from(5u64));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // 0xABCD held in a single 16-bit limb, serialized to two bytes
+        let value = BigUint::from(0xABCDu64);
+        let number = LimbedBigUint::alloc(&mut cs, Some(value.clone()), 1, &params).unwrap();
+
+        let le = number.into_bytes_le(&mut cs, 16).unwrap();
+        assert_eq!(le.len(), 2);
+
+        let restored = LimbedBigUint::from_bytes_le(&mut cs, &le, 16, &params).unwrap();
+        assert_eq!(restored.get_value(), value);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn into_bytes_rejects_too_narrow_width() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // 0x1234 does not fit in a single declared byte
+        let number = alloc(&mut cs, 0x1234, 1, &params);
+        let _ = number.into_bytes_le(&mut cs, 8).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn from_bytes_rejects_wrong_length() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let number = alloc(&mut cs, 0xABCD, 1, &params);
+        let le = number.into_bytes_le(&mut cs, 16).unwrap();
+
+        // declaring a 24-bit width needs three bytes, not two
+        assert!(LimbedBigUint::from_bytes_le(&mut cs, &le, 24, &params).is_err());
+    }
+
+    #[test]
+    fn into_bytes_pads_above_limb_capacity() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // a single 16-bit limb serialized to a wider 32-bit declared width: the
+        // two high bytes are pinned to zero rather than left free
+        let value = BigUint::from(0xABCDu64);
+        let number = LimbedBigUint::alloc(&mut cs, Some(value.clone()), 1, &params).unwrap();
+
+        let le = number.into_bytes_le(&mut cs, 32).unwrap();
+        assert_eq!(le.len(), 4);
+
+        let restored = LimbedBigUint::from_bytes_le(&mut cs, &le, 32, &params).unwrap();
+        assert_eq!(restored.get_value(), value);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn into_bytes_non_aligned_width_fits() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // 0x0F00 = 3840 fits in 12 bits
+        let number = alloc(&mut cs, 0x0F00, 1, &params);
+        let le = number.into_bytes_le(&mut cs, 12).unwrap();
+        assert_eq!(le.len(), 2);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn into_bytes_non_aligned_width_rejects_overflow() {
+        let params = params();
+        let mut cs = new_cs();
+
+        // 0x1000 = 4096 needs 13 bits and must not fit a declared 12-bit width
+        let number = alloc(&mut cs, 0x1000, 1, &params);
+        let _ = number.into_bytes_le(&mut cs, 12).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn is_zero_gadget() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let zero = alloc(&mut cs, 0, 2, &params);
+        let nonzero = alloc(&mut cs, 7, 2, &params);
+
+        let z = zero.is_zero(&mut cs).unwrap();
+        let nz = nonzero.is_zero(&mut cs).unwrap();
+        assert_eq!(z.get_value(), Some(true));
+        assert_eq!(nz.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn equals_gadget() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let a = alloc(&mut cs, 1234, 2, &params);
+        let b = alloc(&mut cs, 1234, 2, &params);
+        let c = alloc(&mut cs, 1235, 2, &params);
+
+        let eq = a.equals(&mut cs, &b).unwrap();
+        let neq = a.equals(&mut cs, &c).unwrap();
+        assert_eq!(eq.get_value(), Some(true));
+        assert_eq!(neq.get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn less_than_gadget() {
+        let params = params();
+        let mut cs = new_cs();
+
+        let small = alloc(&mut cs, 3, 2, &params);
+        let big = alloc(&mut cs, 5, 2, &params);
+
+        assert_eq!(small.less_than(&mut cs, &big).unwrap().get_value(), Some(true));
+        assert_eq!(big.less_than(&mut cs, &small).unwrap().get_value(), Some(false));
+        assert_eq!(small.less_than(&mut cs, &small).unwrap().get_value(), Some(false));
+        assert!(cs.is_satisfied());
+    }
 }
 
 